@@ -0,0 +1,58 @@
+use lazy_static::lazy_static;
+use cpuio::outb;
+use core::arch::asm;
+use x86_64::structures::idt::{InterruptDescriptorTable, InterruptStackFrame};
+use crate::keyboard;
+
+/*The two 8259 PICs default to delivering IRQs on vectors 0x08-0x0f and
+0x70-0x77, which collide with CPU exceptions. They're remapped below to
+the unused 0x20-0x2f range instead.*/
+const PIC1_OFFSET: u8 = 0x20;
+const PIC2_OFFSET: u8 = 0x28;
+
+//The IDT vector the keyboard's IRQ1 line ends up on after the remap.
+pub const KEYBOARD_INTERRUPT_VECTOR: u8 = PIC1_OFFSET + 1;
+
+lazy_static! {
+	static ref IDT: InterruptDescriptorTable = {
+		let mut idt = InterruptDescriptorTable::new();
+		idt[KEYBOARD_INTERRUPT_VECTOR as usize].set_handler_fn(keyboard_interrupt_handler);
+		idt
+	};
+}
+
+/*Loads the IDT, remaps the PICs and unmasks the keyboard line, then turns
+interrupts on. Call once from _start before waiting on any keyboard input.*/
+pub fn init() {
+	IDT.load();
+	remap_pics();
+	unsafe { asm!("sti"); }
+}
+
+/*Sends the standard 8259 init command word (ICW) sequence to both PICs so
+their vector offsets move to PIC1_OFFSET/PIC2_OFFSET, tells them about
+their cascade wiring, and masks every IRQ line except IRQ1 (keyboard).*/
+fn remap_pics() {
+	unsafe {
+		outb(0x11, 0x20); // ICW1: start initialization, expect ICW4
+		outb(0x11, 0xA0);
+		outb(PIC1_OFFSET, 0x21); // ICW2: master PIC vector offset
+		outb(PIC2_OFFSET, 0xA1); // ICW2: slave PIC vector offset
+		outb(0x04, 0x21); // ICW3: tell master there's a slave on IRQ2
+		outb(0x02, 0xA1); // ICW3: tell slave its cascade identity
+		outb(0x01, 0x21); // ICW4: 8086 mode
+		outb(0x01, 0xA1);
+		outb(0xfd, 0x21); // mask everything on the master except IRQ1 (keyboard)
+		outb(0xff, 0xA1); // mask everything on the slave, nothing routed through it yet
+	}
+}
+
+//Tells the master PIC the current interrupt has been handled.
+fn end_of_interrupt() {
+	unsafe { outb(0x20, 0x20); }
+}
+
+extern "x86-interrupt" fn keyboard_interrupt_handler(_stack_frame: InterruptStackFrame) {
+	keyboard::handle_scancode_interrupt();
+	end_of_interrupt();
+}
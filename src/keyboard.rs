@@ -0,0 +1,129 @@
+use core::sync::atomic::{AtomicU8, AtomicUsize, Ordering};
+use lazy_static::lazy_static;
+use spin::Mutex;
+use cpuio::inb;
+use pc_keyboard::{layouts, DecodedKey, HandleControl, KeyCode, Keyboard, ScancodeSet1};
+use crate::WRITER;
+use crate::vga_buffer::char_to_cp437;
+
+//The data port of the 8042 PS/2 controller, where a pending scancode is read from.
+const DATA_PORT: u16 = 0x60;
+
+//How many lines PageUp/PageDown scroll the screen by.
+const SCROLL_STEP: usize = 20;
+
+/*The number of slots in the ring. One slot always stays empty to tell a
+full ring from an empty one, so it holds at most RING_CAPACITY - 1 decoded
+characters between interrupt and consumer.*/
+const RING_CAPACITY: usize = 256;
+
+/*A fixed-capacity single-producer/single-consumer ring buffer, lock-free
+via a pair of head/tail atomics instead of a Mutex. The keyboard interrupt
+handler is the producer; read_line() running on the main kernel "thread"
+is the consumer.*/
+struct CharRing {
+	buffer: [AtomicU8; RING_CAPACITY],
+	head: AtomicUsize,
+	tail: AtomicUsize,
+}
+
+impl CharRing {
+	fn push(&self, byte: u8) {
+		let head = self.head.load(Ordering::Relaxed);
+		let next = (head + 1) % RING_CAPACITY;
+		if next == self.tail.load(Ordering::Acquire) {
+			return; // ring is full, drop the character
+		}
+		self.buffer[head].store(byte, Ordering::Relaxed);
+		self.head.store(next, Ordering::Release);
+	}
+	fn pop(&self) -> Option<u8> {
+		let tail = self.tail.load(Ordering::Relaxed);
+		if tail == self.head.load(Ordering::Acquire) {
+			return None;
+		}
+		let byte = self.buffer[tail].load(Ordering::Relaxed);
+		self.tail.store((tail + 1) % RING_CAPACITY, Ordering::Release);
+		Some(byte)
+	}
+}
+
+lazy_static! {
+	static ref KEY_RING: CharRing = CharRing {
+		buffer: [(); RING_CAPACITY].map(|_| AtomicU8::new(0)),
+		head: AtomicUsize::new(0),
+		tail: AtomicUsize::new(0),
+	};
+	static ref KEYBOARD: Mutex<Keyboard<layouts::Us104Key, ScancodeSet1>> =
+		Mutex::new(Keyboard::new(ScancodeSet1::new(), layouts::Us104Key, HandleControl::Ignore));
+}
+
+/*Called from the keyboard interrupt handler. Reads the pending scancode
+straight off the 8042 data port, runs it through the scancode-set-1
+decoder (which tracks shift/caps state across calls), and pushes any
+resulting character onto the ring buffer so the interrupt handler itself
+stays short.*/
+pub fn handle_scancode_interrupt() {
+	let scancode = unsafe { inb(DATA_PORT) };
+	let mut keyboard = KEYBOARD.lock();
+	if let Ok(Some(event)) = keyboard.add_byte(scancode) {
+		if let Some(key) = keyboard.process_keyevent(event) {
+			match key {
+				// same CP437 translation write_string uses, so a raw `as u8`
+				// cast can't silently truncate a non-ASCII character
+				DecodedKey::Unicode(character) => KEY_RING.push(char_to_cp437(character)),
+				DecodedKey::RawKey(KeyCode::PageUp) => crate::scroll_up!(SCROLL_STEP),
+				DecodedKey::RawKey(KeyCode::PageDown) => crate::scroll_down!(SCROLL_STEP),
+				// other raw keys (arrows, function keys, ...) aren't consumed yet
+				DecodedKey::RawKey(_) => {}
+			}
+		}
+	}
+}
+
+//The longest line read_line() will buffer before silently dropping further characters.
+const MAX_LINE_LENGTH: usize = 80;
+
+//A line of input read from the keyboard, as raw bytes since there's no heap to put a String on.
+pub struct Line {
+	bytes: [u8; MAX_LINE_LENGTH],
+	len: usize,
+}
+
+impl Line {
+	pub fn as_str(&self) -> &str {
+		core::str::from_utf8(&self.bytes[..self.len]).unwrap_or("")
+	}
+}
+
+/*Busy-waits on the keyboard ring buffer, echoing each decoded character to
+the WRITER at the current cursor, until Enter is pressed, then returns the
+completed line. Backspace decrements the line and blanks the last cell.*/
+pub fn read_line() -> Line {
+	let mut line = Line { bytes: [0; MAX_LINE_LENGTH], len: 0 };
+	loop {
+		let byte = match KEY_RING.pop() {
+			Some(byte) => byte,
+			None => continue,
+		};
+		match byte {
+			b'\n' => {
+				WRITER.lock().write_byte(b'\n');
+				return line;
+			}
+			0x08 => {
+				if line.len > 0 {
+					line.len -= 1;
+					WRITER.lock().backspace();
+				}
+			}
+			byte if line.len < MAX_LINE_LENGTH => {
+				line.bytes[line.len] = byte;
+				line.len += 1;
+				WRITER.lock().write_byte(byte);
+			}
+			// line already at capacity, drop the extra character
+			_ => {}
+		}
+	}
+}
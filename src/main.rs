@@ -1,15 +1,19 @@
 #![no_std]
 #![no_main]
+#![feature(abi_x86_interrupt)]
 
 use lazy_static::lazy_static;
 use spin::Mutex;
 use core::panic::PanicInfo;
 use cpuio::outb;
 use core::fmt::Write;
-use crate::vga_buffer::{Color, ColorCode, Writer};
+use crate::vga_buffer::{AnsiState, Color, ColorCode, Writer};
 use core::fmt;
 
 mod vga_buffer;
+mod serial;
+mod interrupts;
+mod keyboard;
 
 //Function for panic.
 #[panic_handler]
@@ -26,6 +30,12 @@ lazy_static!{
     		color_code: ColorCode::new(Color::LightGray, Color::Black),
     		buffer: unsafe { &mut *(0xb8000 as *mut vga_buffer::Buffer) },
     		row_position: 0,
+    		ansi_state: AnsiState::Ground,
+    		ansi_params: [0; 4],
+    		ansi_param_count: 0,
+    		scrollback_head: 0,
+    		scrollback_len: 0,
+    		view_offset: 0,
 		});
 }
 
@@ -47,10 +57,14 @@ macro_rules! println {
 
 /*Function for printing to the WRITER.
 Breaks line after each 80th character.
+Also mirrors everything to the serial port, so kernel output stays visible
+even when the screen can't be watched (e.g. a headless QEMU boot run with
+`-serial stdio`).
 Don't call it directly, please use the macro print! or println! instead.*/
 #[doc(hidden)]
 fn _print(args: fmt::Arguments) {
 	WRITER.lock().write_fmt(args).unwrap();
+	serial::SERIAL1.lock().write_fmt(args).unwrap();
 }
 
 /*Macro for _set_color_code. Accepts 2 Color enum parameters.
@@ -71,6 +85,62 @@ fn _set_color_code(c1: Color, c2:Color){
 	WRITER.lock().set_color_code(ColorCode::new(c1, c2));
 }
 
+/*Macro for _set_blink. Accepts a bool parameter.
+true makes text printed after calling this function blink, false turns it back off.
+For more info, check out the _set_blink function.*/
+#[macro_export]
+macro_rules! set_blink {
+	($b:expr) => ($crate::_set_blink($b));
+}
+
+/*Function for toggling the blink attribute of the WRITER.
+Only text printed after calling this function is affected.
+Don't call it directly, please use the macro set_blink! instead.*/
+#[doc(hidden)]
+fn _set_blink(blink: bool){
+	WRITER.lock().set_blink(blink);
+}
+
+/*Macro for _scroll_up. Accepts a lines: usize parameter.
+Scrolls the screen `lines` rows further back into the scrollback history.
+For more info, check out the _scroll_up function.*/
+#[macro_export]
+macro_rules! scroll_up {
+	($lines:expr) => ($crate::_scroll_up($lines));
+}
+
+/*Function for scrolling the WRITER's view back into its scrollback history.
+Uses try_lock instead of lock because this also gets called from the
+keyboard interrupt handler, which must never block on a mutex that the
+interrupted code might already be holding; a scroll request that loses
+the race is simply dropped and not retried.
+Don't call it directly, please use the macro scroll_up! instead.*/
+#[doc(hidden)]
+fn _scroll_up(lines: usize){
+	if let Some(mut writer) = WRITER.try_lock() {
+		writer.scroll_up(lines);
+	}
+}
+
+/*Macro for _scroll_down. Accepts a lines: usize parameter.
+Scrolls the screen `lines` rows back towards the live tail.
+For more info, check out the _scroll_down function.*/
+#[macro_export]
+macro_rules! scroll_down {
+	($lines:expr) => ($crate::_scroll_down($lines));
+}
+
+/*Function for scrolling the WRITER's view back towards the live tail.
+Uses try_lock for the same reason as _scroll_up: it also runs from the
+keyboard interrupt handler and must not block.
+Don't call it directly, please use the macro scroll_down! instead.*/
+#[doc(hidden)]
+fn _scroll_down(lines: usize){
+	if let Some(mut writer) = WRITER.try_lock() {
+		writer.scroll_down(lines);
+	}
+}
+
 /*Macro for setting the cursor position.
 It accepts an x: u16 and an y: u16 parameter.
 0 <= x <= 80
@@ -111,5 +181,10 @@ pub extern "C" fn _start() -> ! {
     set_cursor_position!(0, 24);
 	//TODO macro for write_string_in_row function.
 	WRITER.lock().write_string_in_row("By Mattee, 2019", 23, true);
- 	loop {}
+	WRITER.lock().enable_cursor();
+	interrupts::init();
+	loop {
+		let line = keyboard::read_line();
+		println!("{}", line.as_str());
+	}
 }
@@ -0,0 +1,86 @@
+use lazy_static::lazy_static;
+use spin::Mutex;
+use cpuio::outb;
+use core::fmt;
+
+//The I/O port of the first serial interface (COM1).
+const COM1_PORT: u16 = 0x3F8;
+
+/*A minimal driver for a 16550-compatible UART. Used to mirror kernel
+output to the serial line so it can be read from a host terminal
+(e.g. QEMU's `-serial stdio`) even when the screen isn't visible.*/
+pub struct SerialPort {
+	base: u16,
+}
+
+impl SerialPort {
+	//Builds a driver for the UART living at the given base I/O port and runs its init sequence.
+	fn new(base: u16) -> SerialPort {
+		let port = SerialPort { base };
+		port.init();
+		port
+	}
+	/*Standard 16550 init sequence: disable interrupts, set the divisor
+	latch to get 38400 baud, select 8 bits/no parity/1 stop bit, then
+	enable and clear the FIFOs.*/
+	fn init(&self) {
+		unsafe {
+			outb(0x00, self.base + 1); // disable all interrupts
+			outb(0x80, self.base + 3); // enable DLAB (set baud rate divisor)
+			outb(0x03, self.base + 0); // divisor low byte (3 = 38400 baud)
+			outb(0x00, self.base + 1); // divisor high byte
+			outb(0x03, self.base + 3); // 8 bits, no parity, one stop bit, DLAB off
+			outb(0xc7, self.base + 2); // enable FIFO, clear them, 14-byte threshold
+			outb(0x0b, self.base + 4); // IRQs enabled, RTS/DTR set
+		}
+	}
+	//True once the UART's transmit holding register is empty and ready for another byte.
+	fn is_transmit_empty(&self) -> bool {
+		unsafe { cpuio::inb(self.base + 5) & 0x20 != 0 }
+	}
+	//Writes a single byte out the UART, busy-waiting until it's ready to accept one.
+	fn write_byte(&mut self, byte: u8) {
+		while !self.is_transmit_empty() {}
+		unsafe { outb(byte, self.base) };
+	}
+}
+
+impl fmt::Write for SerialPort {
+	fn write_str(&mut self, s: &str) -> fmt::Result {
+		for byte in s.bytes() {
+			self.write_byte(byte);
+		}
+		Ok(())
+	}
+}
+
+/*The global serial port used for debug output. Mirrors the pattern of
+WRITER in main.rs: accessible through a Mutex, used via
+SERIAL1.lock().<function_name(<parameters>)>.*/
+lazy_static!{
+	pub static ref SERIAL1: Mutex<SerialPort> = Mutex::new(SerialPort::new(COM1_PORT));
+}
+
+/*Macro for _serial_print. Accepts arguments for core::fmt::Write.
+For more info, check out the _serial_print function.*/
+#[macro_export]
+macro_rules! serial_print {
+    ($($arg:tt)*) => ($crate::serial::_serial_print(format_args!($($arg)*)));
+}
+
+/*Macro for _serial_print. Accepts arguments for core::fmt::Write.
+After printing the given data, it moves the cursor to the next line.
+For more info, check out the _serial_print function.*/
+#[macro_export]
+macro_rules! serial_println {
+    () => ($crate::serial_print!("\n"));
+    ($($arg:tt)*) => ($crate::serial_print!("{}\n", format_args!($($arg)*)));
+}
+
+/*Function for printing to the SERIAL1 port.
+Don't call it directly, please use the macro serial_print! or serial_println! instead.*/
+#[doc(hidden)]
+pub fn _serial_print(args: fmt::Arguments) {
+	use core::fmt::Write;
+	SERIAL1.lock().write_fmt(args).unwrap();
+}
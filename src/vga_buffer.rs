@@ -1,5 +1,6 @@
 use volatile::Volatile;
 use core::fmt;
+use cpuio::{inb, outb};
 
 #[allow(dead_code)]
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -21,7 +22,30 @@ pub enum Color {
 	LightRed = 12,
 	Pink = 13,
 	Yellow = 14,
-	White = 15, 
+	White = 15,
+}
+
+/*Maps a 16-color ANSI SGR index (0-15, as used by the 30-37/90-97 and
+40-47/100-107 parameter ranges) onto the matching Color variant.*/
+fn ansi_color(index: u16) -> Color {
+	match index {
+		0 => Color::Black,
+		1 => Color::Red,
+		2 => Color::Green,
+		3 => Color::Brown,
+		4 => Color::Blue,
+		5 => Color::Magenta,
+		6 => Color::Cyan,
+		7 => Color::LightGray,
+		8 => Color::DarkGray,
+		9 => Color::LightRed,
+		10 => Color::LightGreen,
+		11 => Color::Yellow,
+		12 => Color::LightBlue,
+		13 => Color::Pink,
+		14 => Color::LightCyan,
+		_ => Color::White,
+	}
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -38,6 +62,58 @@ impl ColorCode {
 	pub fn new(foreground: Color, background: Color) -> ColorCode{
 		ColorCode((background as u8) << 4 | (foreground as u8))
 	}
+	//Returns a copy of this ColorCode with only the foreground swapped out.
+	fn with_foreground(self, color: Color) -> ColorCode {
+		ColorCode((self.0 & 0xf0) | color as u8)
+	}
+	//Returns a copy of this ColorCode with only the background swapped out.
+	fn with_background(self, color: Color) -> ColorCode {
+		ColorCode((self.0 & 0x0f) | ((color as u8) << 4))
+	}
+	//Returns a copy of this ColorCode with the foreground and background swapped (SGR code 7).
+	fn swapped(self) -> ColorCode {
+		ColorCode((self.0 << 4) | (self.0 >> 4))
+	}
+	/*Returns a copy of this ColorCode with the blink attribute bit toggled
+	(bit 15 of the 16-bit VGA cell, i.e. the top bit of this attribute byte,
+	which is the background nibble's high bit when blink mode is enabled).*/
+	fn with_blink(self, blink: bool) -> ColorCode {
+		if blink {
+			ColorCode(self.0 | 0x80)
+		} else {
+			ColorCode(self.0 & !0x80)
+		}
+	}
+}
+
+/*Unicode code points for code page 437's upper 128 glyphs (bytes
+0x80-0xff): box-drawing characters, accented letters and a handful of
+math/symbol glyphs. Bytes 0x00-0x7f are identical between CP437 and
+ASCII, so only this half needs a table.*/
+const CP437_HIGH: [char; 128] = [
+	'Ç', 'ü', 'é', 'â', 'ä', 'à', 'å', 'ç', 'ê', 'ë', 'è', 'ï', 'î', 'ì', 'Ä', 'Å',
+	'É', 'æ', 'Æ', 'ô', 'ö', 'ò', 'û', 'ù', 'ÿ', 'Ö', 'Ü', '¢', '£', '¥', '₧', 'ƒ',
+	'á', 'í', 'ó', 'ú', 'ñ', 'Ñ', 'ª', 'º', '¿', '⌐', '¬', '½', '¼', '¡', '«', '»',
+	'░', '▒', '▓', '│', '┤', '╡', '╢', '╖', '╕', '╣', '║', '╗', '╝', '╜', '╛', '┐',
+	'└', '┴', '┬', '├', '─', '┼', '╞', '╟', '╚', '╔', '╩', '╦', '╠', '═', '╬', '╧',
+	'╨', '╤', '╥', '╙', '╘', '╒', '╓', '╫', '╪', '┘', '┌', '█', '▄', '▌', '▐', '▀',
+	'α', 'ß', 'Γ', 'π', 'Σ', 'σ', 'µ', 'τ', 'Φ', 'Θ', 'Ω', 'δ', '∞', 'φ', 'ε', '∩',
+	'≡', '±', '≥', '≤', '⌠', '⌡', '÷', '≈', '°', '∙', '·', '√', 'ⁿ', '²', '■', '\u{00a0}',
+];
+
+/*Translates one decoded Unicode scalar value from a UTF-8 &str into the
+matching code page 437 byte the VGA text buffer expects. ASCII passes
+through unchanged; everything else is looked up in CP437_HIGH, falling
+back to 0xfe when the code point has no CP437 glyph.*/
+pub fn char_to_cp437(c: char) -> u8 {
+	let code = c as u32;
+	if code < 0x80 {
+		return code as u8;
+	}
+	match CP437_HIGH.iter().position(|&glyph| glyph == c) {
+		Some(index) => 0x80 + index as u8,
+		None => 0xfe,
+	}
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -52,6 +128,10 @@ struct ScreenChar {
 const BUFFER_HEIGHT: usize = 25;
 //The maximum number of characters in one line.
 const BUFFER_WIDTH: usize = 80;
+//The maximum number of parameters an ANSI CSI sequence can carry before the rest are dropped.
+const MAX_ANSI_PARAMS: usize = 4;
+//The number of rows kept in the off-screen scrollback history.
+const SCROLLBACK_LINES: usize = 500;
 
 #[repr(transparent)]
 /*A 2 dimensional array for storing the characters to display.
@@ -61,6 +141,31 @@ pub struct Buffer {
 	chars: [[Volatile<ScreenChar>; BUFFER_WIDTH]; BUFFER_HEIGHT],
 }
 
+/*The states of the small state machine that recognizes ANSI CSI escape
+sequences (`ESC [ <params> <final>`) inside a byte stream so they can be
+acted on instead of printed as garbage.*/
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AnsiState {
+	Ground,
+	Escape,
+	Csi,
+}
+
+/*The off-screen scrollback ring and the live-screen snapshot taken while
+browsing it are both too large (~80KB and ~4KB) to be fields of Writer:
+Writer itself lives inside a lazy_static, and building it would mean
+assembling one oversized struct literal - with both arrays inlined in
+it - on the kernel's small stack before it gets moved into place. Kept
+as their own top-level statics instead, their initial contents are
+computed at compile time and placed directly in the kernel image's static
+data, never materialized on the stack at all. Access
+is unsynchronized (no Mutex) because the only callers are Writer's own
+methods, which already run under WRITER's lock.*/
+static mut SCROLLBACK: [[ScreenChar; BUFFER_WIDTH]; SCROLLBACK_LINES] =
+	[[ScreenChar { ascii_character: b' ', color_code: ColorCode(0) }; BUFFER_WIDTH]; SCROLLBACK_LINES];
+static mut LIVE_SNAPSHOT: [[ScreenChar; BUFFER_WIDTH]; BUFFER_HEIGHT] =
+	[[ScreenChar { ascii_character: b' ', color_code: ColorCode(0) }; BUFFER_WIDTH]; BUFFER_HEIGHT];
+
 /*The struct for the WRITER object.
 It stores the current cursor position (usize), ColorCode and the buffer
 (which is pointing to 0xb8000, so we can print out text).*/
@@ -69,6 +174,12 @@ pub struct Writer {
 	pub row_position: usize,
 	pub color_code: ColorCode,
 	pub buffer: &'static mut Buffer,
+	pub ansi_state: AnsiState,
+	pub ansi_params: [u16; MAX_ANSI_PARAMS],
+	pub ansi_param_count: usize,
+	pub scrollback_head: usize,
+	pub scrollback_len: usize,
+	pub view_offset: usize,
 }
 
 impl Writer {
@@ -76,6 +187,10 @@ impl Writer {
 	pub fn set_color_code(&mut self, c: ColorCode){
 		self.color_code = c;
 	}
+	//Toggles the blink attribute for future writes; VGA hardware must have blink mode enabled for it to show.
+	pub fn set_blink(&mut self, blink: bool) {
+		self.color_code = self.color_code.with_blink(blink);
+	}
 	pub fn write_string_in_row(&mut self, s: &str, r: usize, clear: bool){
 		if r <= BUFFER_HEIGHT {
 			if clear {
@@ -87,6 +202,11 @@ impl Writer {
 		}
 	}
 	pub fn write_byte(&mut self, byte: u8) {
+		if self.view_offset != 0 {
+			// a new write always snaps the view back to the live tail
+			self.view_offset = 0;
+			self.restore_live();
+		}
 		match byte{
 			b'\n' => self.new_line(),
 			byte => {
@@ -105,9 +225,66 @@ impl Writer {
 				self.column_position += 1;
 			}
 		}
+		self.update_cursor();
+	}
+	/*Writes the current row/column position to the CRTC cursor location
+	registers (index 0x0F/0x0E via ports 0x3D4/0x3D5, the same sequence
+	_set_cursor_position in main.rs uses), so the blinking hardware cursor
+	tracks the text being printed instead of sitting wherever it was last
+	placed manually.*/
+	fn update_cursor(&mut self) {
+		let pos = self.row_position * BUFFER_WIDTH + self.column_position;
+		unsafe {
+			outb(0x0F, 0x3D4);
+			outb((pos & 0xff) as u8, 0x3D5);
+			outb(0x0E, 0x3D4);
+			outb(((pos >> 8) & 0xff) as u8, 0x3D5);
+		}
+	}
+	//Turns the hardware cursor on, shaped as a conventional underline (scanlines 13-14).
+	pub fn enable_cursor(&mut self) {
+		unsafe {
+			outb(0x0A, 0x3D4);
+			let start = inb(0x3D5) & 0xC0;
+			outb(start | 13, 0x3D5);
+			outb(0x0B, 0x3D4);
+			let end = inb(0x3D5) & 0xE0;
+			outb(end | 14, 0x3D5);
+		}
+	}
+	//Turns the hardware cursor off by setting the disable bit in the start-scanline register.
+	pub fn disable_cursor(&mut self) {
+		unsafe {
+			outb(0x0A, 0x3D4);
+			outb(0x20, 0x3D5);
+		}
+	}
+	/*Moves the cursor back one column and blanks that cell. Used by the
+	keyboard line editor to implement Backspace.*/
+	pub fn backspace(&mut self) {
+		if self.view_offset != 0 {
+			// a new edit always snaps the view back to the live tail
+			self.view_offset = 0;
+			self.restore_live();
+		}
+		if self.column_position > 0 {
+			self.column_position -= 1;
+			let row = self.row_position;
+			let col = self.column_position;
+			self.buffer.chars[row][col].write(ScreenChar {
+				ascii_character: b' ',
+				color_code: self.color_code,
+			});
+			self.update_cursor();
+		}
 	}
 	fn new_line(&mut self){
 		if self.row_position >= BUFFER_HEIGHT - 2{
+			let mut evicted = [ScreenChar { ascii_character: b' ', color_code: self.color_code }; BUFFER_WIDTH];
+			for col in 0..BUFFER_WIDTH {
+				evicted[col] = self.buffer.chars[0][col].read();
+			}
+			self.push_scrollback(evicted);
 			for row in 1..BUFFER_HEIGHT {
 				for col in 0..BUFFER_WIDTH {
 					let character = self.buffer.chars[row][col].read();
@@ -119,7 +296,82 @@ impl Writer {
 			self.row_position += 1;
 		}
         self.column_position = 0;
+        self.update_cursor();
     }
+	//Stores a row being scrolled off the top of the screen into the scrollback ring.
+	fn push_scrollback(&mut self, line: [ScreenChar; BUFFER_WIDTH]) {
+		unsafe {
+			SCROLLBACK[self.scrollback_head] = line;
+		}
+		self.scrollback_head = (self.scrollback_head + 1) % SCROLLBACK_LINES;
+		if self.scrollback_len < SCROLLBACK_LINES {
+			self.scrollback_len += 1;
+		}
+	}
+	//Reads back the `index`-th oldest line still held in the scrollback ring (0 = oldest).
+	fn scrollback_line(&self, index: usize) -> [ScreenChar; BUFFER_WIDTH] {
+		let physical = (self.scrollback_head + SCROLLBACK_LINES - self.scrollback_len + index) % SCROLLBACK_LINES;
+		unsafe { SCROLLBACK[physical] }
+	}
+	//Saves the currently visible 25 rows so they can be restored once scrollback browsing ends.
+	fn capture_live(&mut self) {
+		for row in 0..BUFFER_HEIGHT {
+			for col in 0..BUFFER_WIDTH {
+				let character = self.buffer.chars[row][col].read();
+				unsafe {
+					LIVE_SNAPSHOT[row][col] = character;
+				}
+			}
+		}
+	}
+	//Repaints the screen with the saved live snapshot, i.e. what was visible before scrolling.
+	fn restore_live(&mut self) {
+		for row in 0..BUFFER_HEIGHT {
+			for col in 0..BUFFER_WIDTH {
+				let character = unsafe { LIVE_SNAPSHOT[row][col] };
+				self.buffer.chars[row][col].write(character);
+			}
+		}
+	}
+	/*Repaints the screen for the current view_offset: the top
+	view_offset rows come from the scrollback ring, the rest from the
+	live snapshot taken when browsing started.*/
+	fn repaint_history(&mut self) {
+		let start = self.scrollback_len - self.view_offset;
+		for screen_row in 0..BUFFER_HEIGHT {
+			let history_row = start + screen_row;
+			let line = if history_row < self.scrollback_len {
+				self.scrollback_line(history_row)
+			} else {
+				unsafe { LIVE_SNAPSHOT[history_row - self.scrollback_len] }
+			};
+			for col in 0..BUFFER_WIDTH {
+				self.buffer.chars[screen_row][col].write(line[col]);
+			}
+		}
+	}
+	/*Scrolls the view `lines` rows further back into history, clamped to
+	how much scrollback actually exists, and repaints the screen from it.*/
+	pub fn scroll_up(&mut self, lines: usize) {
+		if self.view_offset == 0 {
+			self.capture_live();
+		}
+		self.view_offset = (self.view_offset + lines).min(self.scrollback_len);
+		self.repaint_history();
+	}
+	/*Scrolls the view `lines` rows back towards the present; once it
+	reaches the live tail the original screen content is restored.*/
+	pub fn scroll_down(&mut self, lines: usize) {
+		if self.view_offset == 0 {
+			return;
+		}
+		self.view_offset = self.view_offset.saturating_sub(lines);
+		if self.view_offset == 0 {
+			self.restore_live();
+		} else {
+			self.repaint_history();
+		}
+	}
 	fn clear_row(&mut self, row: usize) {
         let blank = ScreenChar {
             ascii_character: b' ',
@@ -130,16 +382,121 @@ impl Writer {
         }
     }
     pub fn write_string(&mut self, s: &str) {
-        for byte in s.bytes() {
-            match byte {
-                // printable ASCII byte or newline
-                0x20..=0x7e | b'\n' => self.write_byte(byte),
-                // not part of printable ASCII range
-                _ => self.write_byte(0xfe),
-            }
-
+        for c in s.chars() {
+            self.write_cp437_byte(char_to_cp437(c));
         }
     }
+	/*Feeds a single already-CP437-encoded byte through the ANSI escape
+	state machine. Bytes that are part of a recognized `ESC [ ... <final>`
+	sequence are buffered and consumed here instead of reaching the
+	screen; everything else falls through to the ordinary printable/
+	fallback handling. Use this directly for code that already has raw
+	CP437 bytes in hand; write_string goes
+	through here too, after translating UTF-8 into CP437.*/
+	pub fn write_cp437_byte(&mut self, byte: u8) {
+		match self.ansi_state {
+			AnsiState::Ground => {
+				if byte == 0x1b {
+					self.reset_ansi();
+					self.ansi_state = AnsiState::Escape;
+				} else {
+					match byte {
+						// printable ASCII, newline, or a CP437 high-range glyph
+						0x20..=0x7e | b'\n' | 0x80..=0xff => self.write_byte(byte),
+						// not part of printable ASCII and not a CP437 glyph
+						_ => self.write_byte(0xfe),
+					}
+				}
+			}
+			AnsiState::Escape => {
+				if byte == b'[' {
+					self.ansi_state = AnsiState::Csi;
+				} else {
+					// not a CSI sequence we understand, silently drop it
+					self.ansi_state = AnsiState::Ground;
+				}
+			}
+			AnsiState::Csi => {
+				match byte {
+					b'0'..=b'9' => self.ansi_push_digit(byte),
+					b';' => self.ansi_next_param(),
+					// final bytes of a CSI sequence live in 0x40..=0x7e
+					0x40..=0x7e => {
+						self.execute_csi(byte);
+						self.ansi_state = AnsiState::Ground;
+					}
+					// malformed sequence, silently drop it
+					_ => self.ansi_state = AnsiState::Ground,
+				}
+			}
+		}
+	}
+	fn reset_ansi(&mut self) {
+		self.ansi_params = [0; MAX_ANSI_PARAMS];
+		self.ansi_param_count = 0;
+	}
+	fn ansi_push_digit(&mut self, digit: u8) {
+		if self.ansi_param_count == 0 {
+			self.ansi_param_count = 1;
+		}
+		if let Some(param) = self.ansi_params.get_mut(self.ansi_param_count - 1) {
+			*param = param.saturating_mul(10).saturating_add((digit - b'0') as u16);
+		}
+	}
+	fn ansi_next_param(&mut self) {
+		if self.ansi_param_count == 0 {
+			// the ';' closes a leading empty parameter (defaults to 0)
+			self.ansi_param_count = 1;
+		}
+		// counting one past MAX_ANSI_PARAMS makes ansi_push_digit drop the extra digits
+		if self.ansi_param_count <= MAX_ANSI_PARAMS {
+			self.ansi_param_count += 1;
+		}
+	}
+	//Runs the action for a complete CSI sequence, dispatching on its final byte.
+	fn execute_csi(&mut self, final_byte: u8) {
+		match final_byte {
+			b'm' => self.apply_sgr(),
+			b'H' | b'f' => self.apply_cursor_position(),
+			b'J' => {
+				if self.ansi_param_count > 0 && self.ansi_params[0] == 2 {
+					for row in 0..BUFFER_HEIGHT {
+						self.clear_row(row);
+					}
+				}
+			}
+			b'K' => self.clear_row(self.row_position),
+			// unsupported final byte, ignore
+			_ => {}
+		}
+	}
+	//Applies an SGR (`m`) sequence's parameters to color_code, one at a time.
+	fn apply_sgr(&mut self) {
+		if self.ansi_param_count == 0 {
+			self.color_code = ColorCode::new(Color::LightGray, Color::Black);
+			return;
+		}
+		for i in 0..self.ansi_param_count.min(MAX_ANSI_PARAMS) {
+			match self.ansi_params[i] {
+				0 => self.color_code = ColorCode::new(Color::LightGray, Color::Black),
+				7 => self.color_code = self.color_code.swapped(),
+				code @ 30..=37 => self.color_code = self.color_code.with_foreground(ansi_color(code - 30)),
+				code @ 90..=97 => self.color_code = self.color_code.with_foreground(ansi_color(code - 90 + 8)),
+				code @ 40..=47 => self.color_code = self.color_code.with_background(ansi_color(code - 40)),
+				code @ 100..=107 => self.color_code = self.color_code.with_background(ansi_color(code - 100 + 8)),
+				// unsupported SGR code, ignore
+				_ => {}
+			}
+		}
+	}
+	//Applies an `H`/`f` cursor-position sequence; params are 1-based and default to row 1, column 1.
+	fn apply_cursor_position(&mut self) {
+		let row = if self.ansi_param_count > 0 { self.ansi_params[0] } else { 1 };
+		let col = if self.ansi_param_count > 1 { self.ansi_params[1] } else { 1 };
+		self.row_position = (row.max(1) as usize - 1).min(BUFFER_HEIGHT - 1);
+		self.column_position = (col.max(1) as usize - 1).min(BUFFER_WIDTH - 1);
+		self.update_cursor();
+	}
 }
 
 impl fmt::Write for Writer {
@@ -147,4 +504,4 @@ impl fmt::Write for Writer {
 		self.write_string(s);
 		Ok(())
 	}
-}
\ No newline at end of file
+}